@@ -0,0 +1,71 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Withdraw amount exceeds balance")]
+    WithdrawAmountExceedsBalance {},
+
+    #[error("No recipients specified")]
+    NoRecipients {},
+
+    #[error("Recipient weight must be non-zero")]
+    InvalidWeight {},
+
+    #[error("Insufficient vault shares")]
+    InsufficientShares {},
+
+    #[error("Vault holds no balance to price shares against")]
+    NoVaultBalance {},
+
+    #[error("Withdrawal amount exceeds the contract's actual cw20 balance")]
+    InsufficientContractBalance {},
+
+    #[error("cw20_addr does not match the configured token")]
+    Cw20AddressMismatch {},
+
+    #[error("input_token does not match either pool token")]
+    InvalidInputToken {},
+
+    #[error("Swap output is below the minimum specified (slippage)")]
+    SlippageExceeded {},
+
+    #[error("Required token2 amount exceeds max_token2")]
+    MaxToken2Exceeded {},
+
+    #[error("fee_bps must not exceed 10000 (100%)")]
+    InvalidFeeBps {},
+
+    #[error("Insufficient LP shares")]
+    InsufficientLpShares {},
+
+    #[error("Campaign funding deadline has passed")]
+    CampaignEnded {},
+
+    #[error("Campaign deadline has not passed yet")]
+    CampaignStillActive {},
+
+    #[error("Campaign funds have already been claimed")]
+    AlreadyClaimed {},
+
+    #[error("Campaign did not raise enough to meet its goal")]
+    GoalNotMet {},
+
+    #[error("Campaign met its goal, so contributions are not refundable")]
+    GoalMet {},
+
+    #[error("Caller has no recorded contribution to refund")]
+    NoContribution {},
+
+    #[error("Cannot migrate from a different contract: {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("Cannot migrate from a newer contract version: {previous_version}")]
+    CannotMigrateVersion { previous_version: String },
+}
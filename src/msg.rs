@@ -1,25 +1,90 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Timestamp, Uint128};
+use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub cw20_addr: String, // allowed cw20 token
+    // AMM pool tokens and swap fee, in basis points (e.g. 30 = 0.3%)
+    pub token1_addr: String,
+    pub token2_addr: String,
+    pub fee_bps: u64,
+    // Crowdfunding campaign parameters, denominated in cw20_addr
+    pub goal: Uint128,
+    pub deadline: Timestamp,
+    pub beneficiary: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    // Required to backfill AMM_STATE when migrating a contract instantiated
+    // before the AMM feature existed (so AMM_STATE was never saved). Ignored
+    // if AMM_STATE is already present.
+    pub token1_addr: Option<String>,
+    pub token2_addr: Option<String>,
+    pub fee_bps: Option<u64>,
+    // Required to backfill CAMPAIGN_STATE when migrating a contract instantiated
+    // before the crowdfunding feature existed. Ignored if CAMPAIGN_STATE is
+    // already present.
+    pub goal: Option<Uint128>,
+    pub deadline: Option<Timestamp>,
+    pub beneficiary: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    SendCoinsToContract {
+    // Entry point the configured cw20 token (for Split/Fund) or either AMM pool
+    // token (for Swap) calls after a `Send` - carries the original sender, the
+    // amount transferred, and a ReceiveMsg hook describing what to do with it
+    Receive(Cw20ReceiveMsg),
+    WithdrawCoinsFromContract {
         amount: Uint128,
         cw20_addr: String,
-        recipient1: String,
-        recipient2: String,
     },
-    WithdrawCoinsFromContract {
+    // Deposit cw20 tokens into the vault and mint shares proportional to the vault's balance
+    Deposit {
         amount: Uint128,
-        cw20_addr: String,
     },
+    // Burn vault shares and withdraw the corresponding pro-rata amount of cw20 tokens
+    Withdraw {
+        shares: Uint128,
+    },
+    // Add liquidity to the token1/token2 AMM pool. The first provider sets the
+    // initial price; later providers must supply token2 in the current reserve
+    // ratio, up to max_token2, and mint LP shares proportional to their share of
+    // reserve1. Pulls both tokens via TransferFrom (allowance required for both),
+    // since adding liquidity moves two different tokens atomically in one call -
+    // unlike Swap/Fund, that can't be expressed as a single cw20 Send/Receive
+    AddLiquidity {
+        token1_amount: Uint128,
+        max_token2: Uint128,
+    },
+    // Burn lp_amount LP shares and withdraw the corresponding pro-rata slice of both reserves
+    RemoveLiquidity {
+        lp_amount: Uint128,
+    },
+    // Send the full raised pot to the beneficiary, once the goal is met and the deadline has passed
+    Claim {},
+    // Reclaim exactly the caller's recorded contribution, once the deadline has passed with the goal unmet
+    Refund {},
+}
+
+// Hook messages decoded from Cw20ReceiveMsg::msg
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    // Split the received amount across (recipient, weight) pairs in proportion to
+    // their weights, crediting each recipient's withdraw balance; the rounding
+    // remainder goes to the highest-weight recipient
+    Split { recipients: Vec<(String, u64)> },
+    // Swap the received amount (sent by token1_addr or token2_addr) for the other
+    // pool token, reverting if the output is below min_output (slippage protection)
+    Swap { min_output: Uint128 },
+    // Contribute the received amount (sent by cw20_addr) to the campaign;
+    // rejected once the deadline has passed
+    Fund {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -31,6 +96,21 @@ pub enum QueryMsg {
     GetCw20Address {},
     // GetWithdrawBalance returns withdraw balance
     GetWithdrawBalance { recipient: String },
+    // GetSharesOf returns the vault shares held by address
+    GetSharesOf { address: String },
+    // GetTotalSupply returns total vault shares outstanding
+    GetTotalSupply {},
+    // GetContractBalance returns the contract's real on-chain cw20 balance
+    GetContractBalance {},
+    // GetReserves returns the AMM pool's current token1/token2 reserves
+    GetReserves {},
+    // SimulateSwap previews the output amount for a swap without executing it
+    SimulateSwap {
+        input_token: String,
+        input_amount: Uint128,
+    },
+    // GetCampaignStatus returns the total raised, goal, deadline, and whether it succeeded
+    GetCampaignStatus {},
 }
 
 // We define a custom struct for each query response
@@ -53,3 +133,37 @@ pub struct GetCw20AddressResponse {
 pub struct GetWithdrawBalanceResponse {
     pub withdraw_balance: Uint128,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GetSharesOfResponse {
+    pub shares: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GetTotalSupplyResponse {
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GetContractBalanceResponse {
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GetReservesResponse {
+    pub reserve1: Uint128,
+    pub reserve2: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateSwapResponse {
+    pub output_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GetCampaignStatusResponse {
+    pub total_raised: Uint128,
+    pub goal: Uint128,
+    pub deadline: Timestamp,
+    pub success: bool,
+}
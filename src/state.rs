@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -10,5 +10,53 @@ pub struct State {
     pub cw20_addr: Addr,
 }
 
+// A single (recipient, weight) pair in a fee-split configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SplitRecipient {
+    pub recipient: Addr,
+    pub weight: u64,
+}
+
 pub const STATE: Item<State> = Item::new("state");
 pub const WITHDRAW_BALANCES: Map<&Addr, Uint128> = Map::new("withdraw_balance");
+
+// Total vault shares outstanding
+pub const TOTAL_SUPPLY: Item<Uint128> = Item::new("total_supply");
+// Vault shares held by each depositor
+pub const SHARES: Map<&Addr, Uint128> = Map::new("shares");
+// Cw20 balance owned by the vault itself, credited on deposit and debited on
+// withdraw. Tracked independently of the contract's total cw20 balance, which
+// also holds funds belonging to the fee-split escrow (WITHDRAW_BALANCES) and
+// the crowdfunding pot (CAMPAIGN_STATE/CONTRIBUTIONS) - share price and
+// redemption must only ever be computed against the vault's own funds
+pub const VAULT_BALANCE: Item<Uint128> = Item::new("vault_balance");
+
+// Constant-product (x*y=k) AMM pool between token1 and token2
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AmmState {
+    pub token1_addr: Addr,
+    pub token2_addr: Addr,
+    pub reserve1: Uint128,
+    pub reserve2: Uint128,
+    pub fee_bps: u64,
+}
+
+pub const AMM_STATE: Item<AmmState> = Item::new("amm_state");
+// Total AMM LP shares outstanding
+pub const LP_TOTAL_SUPPLY: Item<Uint128> = Item::new("lp_total_supply");
+// AMM LP shares held by each liquidity provider
+pub const LP_SHARES: Map<&Addr, Uint128> = Map::new("lp_shares");
+
+// Crowdfunding campaign gating fund release on a goal and deadline, denominated in cw20_addr
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CampaignState {
+    pub beneficiary: Addr,
+    pub goal: Uint128,
+    pub deadline: Timestamp,
+    pub total_raised: Uint128,
+    pub claimed: bool,
+}
+
+pub const CAMPAIGN_STATE: Item<CampaignState> = Item::new("campaign_state");
+// Cumulative contribution recorded per contributor, used for refunds if the goal isn't met
+pub const CONTRIBUTIONS: Map<&Addr, Uint128> = Map::new("contributions");